@@ -1,41 +1,69 @@
 use bulletproofs::r1cs::{ConstraintSystem, R1CSError};
 use crate::{merge, range_proof, split};
-use shuffle::{padded_shuffle, value_shuffle};
+use crate::gadgets::{flavor_membership, padded_shuffle, value_shuffle};
+use curve25519_dalek::scalar::Scalar;
 use value::AllocatedValue;
 
 /// Enforces that the outputs are a valid rearrangement of the inputs, following the
 /// soundness and secrecy requirements in the spacesuit transaction spec:
 /// https://github.com/interstellar/spacesuit/blob/master/spec.md
+///
+/// `issuances` are folded into the merge side and `fees` into the split side,
+/// so the conservation check becomes, per flavor:
+/// `Σ inputs + Σ issuances == Σ outputs + Σ fees`. Both lists default to empty
+/// for an ordinary transfer.
+///
+/// If `allowed_flavors` is supplied, every output and issuance is additionally
+/// constrained to carry one of those flavors, without revealing which one.
 pub fn cloak<CS: ConstraintSystem>(
     cs: &mut CS,
     inputs: Vec<AllocatedValue>,
     outputs: Vec<AllocatedValue>,
+    issuances: Vec<AllocatedValue>,
+    fees: Vec<AllocatedValue>,
+    allowed_flavors: Option<&[Scalar]>,
 ) -> Result<(), R1CSError> {
+    let mut merge_inputs = inputs;
+    merge_inputs.extend(issuances.iter().cloned());
+
+    let mut split_outputs = outputs.clone();
+    split_outputs.extend(fees.iter().cloned());
+
     // Merge
-    let (merge_in, merge_out) = merge::fill_cs(cs, inputs.clone())?;
+    let (merge_in, merge_out) = merge::fill_cs(cs, merge_inputs.clone())?;
 
     // Split
-    let (split_out, split_in) = split::fill_cs(cs, outputs.clone())?;
+    let (split_out, split_in) = split::fill_cs(cs, split_outputs.clone())?;
 
     // Shuffle 1
-    // Check that `merge_in` is a valid reordering of `inputs`
-    // when `inputs` are grouped by flavor.
-    value_shuffle(cs, inputs, merge_in)?;
+    // Check that `merge_in` is a valid reordering of `merge_inputs` (the
+    // transfer inputs plus any issuances) when grouped by flavor.
+    value_shuffle::fill_cs(cs, merge_inputs, merge_in)?;
 
     // Shuffle 2
     // Check that `split_in` is a valid reordering of `merge_out`, allowing for
     // the adding or dropping of padding values (quantity = 0) if m != n.
-    padded_shuffle(cs, merge_out, split_in)?;
+    padded_shuffle::fill_cs(cs, merge_out, split_in)?;
 
     // Shuffle 3
-    // Check that `split_out` is a valid reordering of `outputs`
-    // when `outputs` are grouped by flavor.
-    value_shuffle(cs, split_out, outputs.clone())?;
+    // Check that `split_out` is a valid reordering of `split_outputs` (the
+    // transfer outputs plus any fees) when grouped by flavor.
+    value_shuffle::fill_cs(cs, split_out, split_outputs.clone())?;
 
     // Range Proof
-    // Check that each of the quantities in `outputs` lies in [0, 2^64).
-    for output in outputs {
-        range_proof(cs, output.quantity(), 64)?;
+    // Check that each of the outputs and fees lies in [0, 2^64).
+    for value in &split_outputs {
+        range_proof(cs, value.quantity(), 64)?;
+    }
+
+    // Flavor Whitelist
+    // If the caller supplied a public set of allowed flavors, check that each
+    // output and issuance carries one of them. Fees are deliberately excluded:
+    // a fee/burn value need not be a sanctioned transfer flavor.
+    if let Some(allowed) = allowed_flavors {
+        for value in outputs.iter().chain(issuances.iter()) {
+            flavor_membership::fill_cs(cs, *value, allowed)?;
+        }
     }
 
     Ok(())
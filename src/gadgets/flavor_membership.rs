@@ -0,0 +1,188 @@
+use bulletproofs::r1cs::{ConstraintSystem, R1CSError, Variable};
+use curve25519_dalek::scalar::Scalar;
+use value::AllocatedValue;
+
+/// Enforces that `value`'s flavor lies in the public set `allowed`, without
+/// revealing which entry it matches.
+///
+/// For a set of size `n`, this constrains `∏_j (f - allowed_j) == 0` using a
+/// chain of `n-1` multipliers (the same chained-multiply structure used by
+/// the shuffle gadgets). The product is zero iff `f` equals one of the
+/// `allowed` scalars.
+pub fn fill_cs<CS: ConstraintSystem>(
+    cs: &mut CS,
+    value: AllocatedValue,
+    allowed: &[Scalar],
+) -> Result<(), R1CSError> {
+    let n = allowed.len();
+
+    if n == 0 {
+        return Err(R1CSError::GadgetError {
+            description: "flavor whitelist must not be empty".to_string(),
+        });
+    }
+
+    // Special case: a single allowed flavor is just an equality constraint.
+    if n == 1 {
+        cs.constrain(value.f - allowed[0]);
+        return Ok(());
+    }
+
+    let (_, _, mut product) = cs.multiply(value.f - allowed[0], value.f - allowed[1]);
+    for allowed_flavor in &allowed[2..] {
+        let (_, _, out) = cs.multiply(product.into(), value.f - *allowed_flavor);
+        product = out;
+    }
+
+    cs.constrain(product.into());
+
+    Ok(())
+}
+
+/// Enforces that `value`'s flavor lies in the public set `allowed`, without
+/// revealing which entry it matches, via a windowed lookup over a
+/// one-hot-encoded index instead of `fill_cs`'s product chain.
+///
+/// Allocates one boolean selector bit `b_j` per entry in `allowed`, each
+/// boolean-constrained via `b_j*(1-b_j) == 0` (`n` multipliers total, one
+/// more than `fill_cs`'s `n-1`). Exactly one bit is constrained to be set
+/// (`Σ b_j == 1`), and the selected entry is tied to `f` by a single free
+/// linear constraint (`Σ b_j*allowed_j == f`) — both of these cost no
+/// multipliers, since `allowed_j` are public scalars. The selector bits stay
+/// internal to this function and are not returned to the caller; `fill_cs`
+/// uses one fewer multiplier.
+pub fn fill_cs_windowed<CS: ConstraintSystem>(
+    cs: &mut CS,
+    value: AllocatedValue,
+    allowed: &[Scalar],
+) -> Result<(), R1CSError> {
+    let n = allowed.len();
+
+    if n == 0 {
+        return Err(R1CSError::GadgetError {
+            description: "flavor whitelist must not be empty".to_string(),
+        });
+    }
+
+    let flavor = value.assignment.map(|v| v.f);
+
+    let mut bits = Vec::with_capacity(n);
+    for allowed_flavor in allowed {
+        let allowed_flavor = *allowed_flavor;
+        let (bit, one_minus_bit, product) = cs.allocate(|| {
+            let flavor = flavor.ok_or_else(|| R1CSError::GadgetError {
+                description: "flavor_membership: missing assignment for selector bit"
+                    .to_string(),
+            })?;
+            let bit = if flavor == allowed_flavor {
+                Scalar::one()
+            } else {
+                Scalar::zero()
+            };
+            Ok((bit, Scalar::one() - bit, Scalar::zero()))
+        })?;
+        cs.constrain(one_minus_bit - (Variable::One() - bit));
+        cs.constrain(product.into());
+        bits.push(bit);
+    }
+
+    let bit_sum = bits
+        .iter()
+        .fold(Variable::One() - Variable::One(), |lc, bit| lc + *bit);
+    cs.constrain(bit_sum - Variable::One());
+
+    let selected = bits.iter().zip(allowed.iter()).fold(
+        Variable::One() - Variable::One(),
+        |lc, (bit, allowed_flavor)| lc + *bit * *allowed_flavor,
+    );
+    cs.constrain(selected - value.f);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bulletproofs::r1cs::{Prover, Verifier};
+    use bulletproofs::{BulletproofGens, PedersenGens};
+    use merlin::Transcript;
+    use value::{ProverCommittable, Value};
+
+    #[test]
+    fn flavor_membership() {
+        let allowed: Vec<Scalar> = vec![666u64.into(), 888u64.into(), 999u64.into()];
+
+        assert!(membership_helper(peso(), allowed.clone(), false).is_ok());
+        assert!(membership_helper(yuan(), allowed.clone(), false).is_ok());
+        assert!(membership_helper(euro(), allowed.clone(), false).is_err());
+        assert!(membership_helper(peso(), vec![888u64.into()], false).is_err());
+    }
+
+    #[test]
+    fn flavor_membership_windowed() {
+        let allowed: Vec<Scalar> = vec![666u64.into(), 888u64.into(), 999u64.into()];
+
+        assert!(membership_helper(peso(), allowed.clone(), true).is_ok());
+        assert!(membership_helper(yuan(), allowed.clone(), true).is_ok());
+        assert!(membership_helper(euro(), allowed.clone(), true).is_err());
+        assert!(membership_helper(peso(), vec![888u64.into()], true).is_err());
+    }
+
+    fn peso() -> Value {
+        Value {
+            q: 1u64.into(),
+            f: 666u64.into(),
+        }
+    }
+    fn yuan() -> Value {
+        Value {
+            q: 1u64.into(),
+            f: 888u64.into(),
+        }
+    }
+    fn euro() -> Value {
+        Value {
+            q: 1u64.into(),
+            f: 123u64.into(),
+        }
+    }
+
+    fn membership_helper(
+        value: Value,
+        allowed: Vec<Scalar>,
+        windowed: bool,
+    ) -> Result<(), R1CSError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(128, 1);
+
+        let (proof, value_com) = {
+            let mut prover_transcript = Transcript::new(b"FlavorMembershipTest");
+            let mut rng = rand::thread_rng();
+            let mut prover = Prover::new(&bp_gens, &pc_gens, &mut prover_transcript);
+
+            let (value_com, value_var) = value.commit(&mut prover, &mut rng);
+
+            if windowed {
+                fill_cs_windowed(&mut prover, value_var, &allowed)?;
+            } else {
+                fill_cs(&mut prover, value_var, &allowed)?;
+            }
+
+            let proof = prover.prove()?;
+            (proof, value_com)
+        };
+
+        let mut verifier_transcript = Transcript::new(b"FlavorMembershipTest");
+        let mut verifier = Verifier::new(&bp_gens, &pc_gens, &mut verifier_transcript);
+
+        let value_var = value_com.commit(&mut verifier);
+
+        if windowed {
+            fill_cs_windowed(&mut verifier, value_var, &allowed)?;
+        } else {
+            fill_cs(&mut verifier, value_var, &allowed)?;
+        }
+
+        Ok(verifier.verify(&proof)?)
+    }
+}
@@ -6,8 +6,9 @@ mod mix;
 mod split;
 mod two_shuffle;
 
-mod padded_shuffle;
+pub(crate) mod flavor_membership;
+pub(crate) mod padded_shuffle;
 mod scalar_shuffle;
-mod value_shuffle;
+pub(crate) mod value_shuffle;
 
 mod range_proof;
@@ -0,0 +1,114 @@
+use bulletproofs::r1cs::{ConstraintSystem, R1CSError, RandomizedConstraintSystem, Variable};
+use curve25519_dalek::scalar::Scalar;
+
+/// Enforces that the values in `y` are a valid reordering of the values in `x`,
+/// without revealing the permutation.
+///
+/// This is the optimal k-shuffle gadget: for a verifier challenge `z`, a list `y`
+/// is a permutation of `x` iff `∏(x_i - z) == ∏(y_i - z)`. Each product is built
+/// as a chain of `k-1` multipliers, so the whole shuffle costs `2*(k-1)`
+/// multipliers.
+pub fn fill_cs<CS: ConstraintSystem>(
+    cs: &mut CS,
+    x: Vec<Variable>,
+    y: Vec<Variable>,
+) -> Result<(), R1CSError> {
+    let k = x.len();
+    if k != y.len() {
+        return Err(R1CSError::GadgetError {
+            description: "x and y vectors have different lengths".to_string(),
+        });
+    }
+
+    // Special case: the 0-shuffle is trivially satisfied.
+    if k == 0 {
+        return Ok(());
+    }
+
+    // Special case: the 1-shuffle doesn't need a challenge, just an equality.
+    if k == 1 {
+        cs.constrain(y[0] - x[0]);
+        return Ok(());
+    }
+
+    cs.specify_randomized_constraints(move |cs| {
+        let z = cs.challenge_scalar(b"shuffle challenge");
+
+        let x_product = product_of_shifted(cs, &x, z);
+        let y_product = product_of_shifted(cs, &y, z);
+
+        cs.constrain(x_product - y_product);
+
+        Ok(())
+    })
+}
+
+/// Chains `k-1` multipliers to compute `∏(v_i - z)` for `v.len() == k >= 2`.
+pub(super) fn product_of_shifted<CS: RandomizedConstraintSystem>(
+    cs: &mut CS,
+    v: &[Variable],
+    z: Scalar,
+) -> Variable {
+    let k = v.len();
+    let (_, _, mut out) = cs.multiply(v[k - 1] - z, v[k - 2] - z);
+    for i in (0..k - 2).rev() {
+        let (_, _, o) = cs.multiply(out.into(), v[i] - z);
+        out = o;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bulletproofs::r1cs::{Prover, Verifier};
+    use bulletproofs::{BulletproofGens, PedersenGens};
+    use merlin::Transcript;
+
+    #[test]
+    fn scalar_shuffle() {
+        assert!(shuffle_helper(vec![], vec![]).is_ok());
+        assert!(shuffle_helper(vec![3], vec![3]).is_ok());
+        assert!(shuffle_helper(vec![3], vec![6]).is_err());
+        assert!(shuffle_helper(vec![3, 6], vec![6, 3]).is_ok());
+        assert!(shuffle_helper(vec![3, 6], vec![3, 6]).is_ok());
+        assert!(shuffle_helper(vec![3, 6], vec![6, 6]).is_err());
+        assert!(shuffle_helper(vec![1, 2, 3, 4, 5], vec![5, 4, 3, 2, 1]).is_ok());
+        assert!(shuffle_helper(vec![1, 2, 3, 4, 5], vec![1, 2, 3, 4, 6]).is_err());
+    }
+
+    fn shuffle_helper(input: Vec<u64>, output: Vec<u64>) -> Result<(), R1CSError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(128, 1);
+
+        let (proof, input_com, output_com) = {
+            let mut prover_transcript = Transcript::new(b"ScalarShuffleTest");
+            let mut rng = rand::thread_rng();
+            let mut prover = Prover::new(&bp_gens, &pc_gens, &mut prover_transcript);
+
+            let (input_com, input_vars): (Vec<_>, Vec<_>) = input
+                .iter()
+                .map(|v| prover.commit(Scalar::from(*v), Scalar::random(&mut rng)))
+                .unzip();
+            let (output_com, output_vars): (Vec<_>, Vec<_>) = output
+                .iter()
+                .map(|v| prover.commit(Scalar::from(*v), Scalar::random(&mut rng)))
+                .unzip();
+
+            fill_cs(&mut prover, input_vars, output_vars)?;
+
+            let proof = prover.prove()?;
+            (proof, input_com, output_com)
+        };
+
+        let mut verifier_transcript = Transcript::new(b"ScalarShuffleTest");
+        let mut verifier = Verifier::new(&bp_gens, &pc_gens, &mut verifier_transcript);
+
+        let input_vars: Vec<_> = input_com.iter().map(|c| verifier.commit(*c)).collect();
+        let output_vars: Vec<_> = output_com.iter().map(|c| verifier.commit(*c)).collect();
+
+        fill_cs(&mut verifier, input_vars, output_vars)?;
+
+        Ok(verifier.verify(&proof)?)
+    }
+}
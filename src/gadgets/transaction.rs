@@ -0,0 +1,19 @@
+use bulletproofs::r1cs::{ConstraintSystem, R1CSError};
+use curve25519_dalek::scalar::Scalar;
+use value::AllocatedValue;
+
+/// Wires up the full cloaked-transaction constraint set: shuffle, merge,
+/// shuffle, split, shuffle, and range-check the outputs and fees. See
+/// `cloak::cloak` for the
+/// details of each stage, including the optional `issuances`, `fees`, and
+/// flavor whitelist.
+pub fn fill_cs<CS: ConstraintSystem>(
+    cs: &mut CS,
+    inputs: Vec<AllocatedValue>,
+    outputs: Vec<AllocatedValue>,
+    issuances: Vec<AllocatedValue>,
+    fees: Vec<AllocatedValue>,
+    allowed_flavors: Option<&[Scalar]>,
+) -> Result<(), R1CSError> {
+    crate::cloak::cloak(cs, inputs, outputs, issuances, fees, allowed_flavors)
+}
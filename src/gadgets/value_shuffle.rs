@@ -0,0 +1,148 @@
+use bulletproofs::r1cs::{ConstraintSystem, LinearCombination, R1CSError, RandomizedConstraintSystem};
+use curve25519_dalek::scalar::Scalar;
+use value::AllocatedValue;
+
+/// Enforces that the values in `y` are a valid reordering of the values in `x`,
+/// without revealing the permutation.
+///
+/// Each `AllocatedValue`'s `(q, f)` pair is first folded into a single scalar
+/// wire `v_i = q_i + c*f_i`, using a challenge `c` drawn from the transcript
+/// (one multiplier per value). The folded wires are then run through the same
+/// product-polynomial check as `scalar_shuffle`, using a second, independent
+/// challenge `z`.
+pub fn fill_cs<CS: ConstraintSystem>(
+    cs: &mut CS,
+    x: Vec<AllocatedValue>,
+    y: Vec<AllocatedValue>,
+) -> Result<(), R1CSError> {
+    let k = x.len();
+    if k != y.len() {
+        return Err(R1CSError::GadgetError {
+            description: "x and y vectors have different lengths".to_string(),
+        });
+    }
+
+    // Special case: the 0-shuffle is trivially satisfied.
+    if k == 0 {
+        return Ok(());
+    }
+
+    // Special case: the 1-shuffle doesn't need any challenges, just equality
+    // of both fields.
+    if k == 1 {
+        cs.constrain(y[0].q - x[0].q);
+        cs.constrain(y[0].f - x[0].f);
+        return Ok(());
+    }
+
+    cs.specify_randomized_constraints(move |cs| {
+        let c = cs.challenge_scalar(b"value shuffle fold challenge");
+        let z = cs.challenge_scalar(b"value shuffle challenge");
+
+        let x_folded: Vec<LinearCombination> =
+            x.iter().map(|v| fold(cs, v, c)).collect();
+        let y_folded: Vec<LinearCombination> =
+            y.iter().map(|v| fold(cs, v, c)).collect();
+
+        let x_product = product_of_shifted(cs, x_folded, z);
+        let y_product = product_of_shifted(cs, y_folded, z);
+
+        cs.constrain(x_product - y_product);
+
+        Ok(())
+    })
+}
+
+/// Folds `(q, f)` into a single wire `q + c*f`, spending one multiplier to
+/// compute `c*f`.
+fn fold<CS: RandomizedConstraintSystem>(
+    cs: &mut CS,
+    value: &AllocatedValue,
+    c: Scalar,
+) -> LinearCombination {
+    let (_, _, c_times_f) = cs.multiply(c.into(), value.f.into());
+    value.q + c_times_f
+}
+
+/// Chains `k-1` multipliers to compute `∏(v_i - z)` for `v.len() == k >= 2`.
+fn product_of_shifted<CS: RandomizedConstraintSystem>(
+    cs: &mut CS,
+    mut v: Vec<LinearCombination>,
+    z: Scalar,
+) -> LinearCombination {
+    let k = v.len();
+    let last = v.pop().unwrap();
+    let second_to_last = v.pop().unwrap();
+    let (_, _, mut out) = cs.multiply(last - z, second_to_last - z);
+    for lc in v.into_iter().rev().take(k.saturating_sub(2)) {
+        let (_, _, o) = cs.multiply(out.into(), lc - z);
+        out = o;
+    }
+    out.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bulletproofs::r1cs::{Prover, Verifier};
+    use bulletproofs::{BulletproofGens, PedersenGens};
+    use merlin::Transcript;
+    use value::{ProverCommittable, Value, VerifierCommittable};
+
+    #[test]
+    fn value_shuffle() {
+        assert!(shuffle_helper(vec![], vec![]).is_ok());
+        assert!(shuffle_helper(vec![peso(1)], vec![peso(1)]).is_ok());
+        assert!(shuffle_helper(vec![peso(1)], vec![peso(2)]).is_err());
+        assert!(shuffle_helper(vec![peso(1), yuan(4)], vec![yuan(4), peso(1)]).is_ok());
+        assert!(shuffle_helper(vec![peso(1), yuan(4)], vec![peso(1), yuan(4)]).is_ok());
+        assert!(shuffle_helper(vec![peso(1), yuan(4)], vec![peso(4), yuan(1)]).is_err());
+        assert!(shuffle_helper(
+            vec![peso(1), yuan(4), peso(8)],
+            vec![yuan(4), peso(8), peso(1)]
+        )
+        .is_ok());
+    }
+
+    fn yuan(q: u64) -> Value {
+        Value {
+            q: q.into(),
+            f: 888u64.into(),
+        }
+    }
+    fn peso(q: u64) -> Value {
+        Value {
+            q: q.into(),
+            f: 666u64.into(),
+        }
+    }
+
+    fn shuffle_helper(input: Vec<Value>, output: Vec<Value>) -> Result<(), R1CSError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(128, 1);
+
+        let (proof, input_com, output_com) = {
+            let mut prover_transcript = Transcript::new(b"ValueShuffleTest");
+            let mut rng = rand::thread_rng();
+            let mut prover = Prover::new(&bp_gens, &pc_gens, &mut prover_transcript);
+
+            let (input_com, input_vars) = input.commit(&mut prover, &mut rng);
+            let (output_com, output_vars) = output.commit(&mut prover, &mut rng);
+
+            fill_cs(&mut prover, input_vars, output_vars)?;
+
+            let proof = prover.prove()?;
+            (proof, input_com, output_com)
+        };
+
+        let mut verifier_transcript = Transcript::new(b"ValueShuffleTest");
+        let mut verifier = Verifier::new(&bp_gens, &pc_gens, &mut verifier_transcript);
+
+        let input_vars = input_com.commit(&mut verifier);
+        let output_vars = output_com.commit(&mut verifier);
+
+        fill_cs(&mut verifier, input_vars, output_vars)?;
+
+        Ok(verifier.verify(&proof)?)
+    }
+}
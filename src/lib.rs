@@ -2,6 +2,9 @@ extern crate bulletproofs;
 extern crate curve25519_dalek;
 extern crate merlin;
 extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 extern crate subtle;
 
 mod k_mix;
@@ -9,17 +12,19 @@ mod merge;
 mod mix;
 mod split;
 
-mod padded_shuffle;
-mod scalar_shuffle;
-mod value_shuffle;
+mod gadgets;
 
 mod range_proof;
 mod cloak;
+mod spacesuit;
+mod transaction;
 
 mod value;
 
 pub use cloak::cloak;
 pub use range_proof::range_proof;
+pub use spacesuit::{prove, verify, verify_each};
+pub use transaction::Transaction;
 pub use value::{Value, CommittedValue};
 
 // TBD: figure out if we need to export these at all
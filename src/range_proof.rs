@@ -0,0 +1,183 @@
+use bulletproofs::r1cs::{ConstraintSystem, R1CSError, Variable};
+use curve25519_dalek::scalar::Scalar;
+use value::AllocatedQuantity;
+
+/// Enforces that `quantity` lies in `[0, 2^n)`.
+///
+/// Allocates `n` bits `b_i`, constrains each of them to be boolean via
+/// `b_i*(1 - b_i) == 0`, and constrains their weighted sum to equal `quantity`.
+pub fn range_proof<CS: ConstraintSystem>(
+    cs: &mut CS,
+    quantity: AllocatedQuantity,
+    n: usize,
+) -> Result<(), R1CSError> {
+    let magnitude = quantity.assignment.map(|q| q.to_u64().unwrap_or(0));
+    let bits = allocate_bits(cs, magnitude, n)?;
+
+    let weighted_sum = bits
+        .iter()
+        .enumerate()
+        .fold(Variable::One() - Variable::One(), |lc, (i, bit)| {
+            lc + *bit * Scalar::from(1u64 << i)
+        });
+
+    cs.constrain(weighted_sum - quantity.variable);
+
+    Ok(())
+}
+
+/// Enforces that `quantity` lies in `[-(2^n - 1), 2^n - 1]`.
+///
+/// Decomposes the magnitude `|q|` into `n` boolean-constrained bits exactly
+/// like `range_proof`, then carries a boolean sign wire `s` so that the
+/// reconstructed value `(1 - 2s) * |q|` is constrained equal to `quantity`.
+/// Assignment witnesses come from `SignedInteger::to_i128`.
+pub fn range_proof_signed<CS: ConstraintSystem>(
+    cs: &mut CS,
+    quantity: AllocatedQuantity,
+    n: usize,
+) -> Result<(), R1CSError> {
+    let value = quantity.assignment.map(|q| q.to_i128());
+    let magnitude = value.map(|v| v.unsigned_abs() as u64);
+    let is_negative = value.map(|v| v < 0);
+
+    let bits = allocate_bits(cs, magnitude, n)?;
+
+    let magnitude_lc = bits
+        .iter()
+        .enumerate()
+        .fold(Variable::One() - Variable::One(), |lc, (i, bit)| {
+            lc + *bit * Scalar::from(1u64 << i)
+        });
+
+    // Boolean-constrain the sign wire `s`.
+    let (s, one_minus_s, s_product) = cs.allocate(|| {
+        let is_negative = is_negative.ok_or_else(|| R1CSError::GadgetError {
+            description: "range_proof_signed: missing assignment for sign bit".to_string(),
+        })?;
+        let s = if is_negative { Scalar::one() } else { Scalar::zero() };
+        Ok((s, Scalar::one() - s, Scalar::zero()))
+    })?;
+    cs.constrain(one_minus_s - (Variable::One() - s));
+    cs.constrain(s_product.into());
+
+    // Reconstruct `(1 - 2s) * magnitude` with a single multiplier, and tie it
+    // to the allocated quantity.
+    let (_, _, reconstructed) = cs.multiply(Variable::One() - s - s, magnitude_lc);
+    cs.constrain(reconstructed - quantity.variable);
+
+    Ok(())
+}
+
+/// Allocates `n` boolean-constrained bits for `magnitude` (least-significant
+/// bit first).
+fn allocate_bits<CS: ConstraintSystem>(
+    cs: &mut CS,
+    magnitude: Option<u64>,
+    n: usize,
+) -> Result<Vec<Variable>, R1CSError> {
+    let mut bits = Vec::with_capacity(n);
+    for i in 0..n {
+        let (bit, one_minus_bit, product) = cs.allocate(|| {
+            let magnitude = magnitude.ok_or_else(|| R1CSError::GadgetError {
+                description: "range_proof: missing assignment for bit".to_string(),
+            })?;
+            let bit = Scalar::from((magnitude >> i) & 1);
+            Ok((bit, Scalar::one() - bit, Scalar::zero()))
+        })?;
+        cs.constrain(one_minus_bit - (Variable::One() - bit));
+        cs.constrain(product.into());
+        bits.push(bit);
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bulletproofs::r1cs::{Prover, Verifier};
+    use bulletproofs::{BulletproofGens, PedersenGens};
+    use merlin::Transcript;
+    use value::SignedInteger;
+
+    #[test]
+    fn range_proof_unsigned() {
+        assert!(range_proof_helper(0u64.into(), 32).is_ok());
+        assert!(range_proof_helper(255u64.into(), 8).is_ok());
+        assert!(range_proof_helper(256u64.into(), 8).is_err());
+    }
+
+    #[test]
+    fn range_proof_signed_bounds() {
+        assert!(signed_range_proof_helper(SignedInteger::Positive(255), 8).is_ok());
+        assert!(signed_range_proof_helper(SignedInteger::Negative(255), 8).is_ok());
+        assert!(signed_range_proof_helper(SignedInteger::Zero, 8).is_ok());
+        assert!(signed_range_proof_helper(SignedInteger::Negative(256), 8).is_err());
+    }
+
+    fn range_proof_helper(quantity: SignedInteger, n: usize) -> Result<(), R1CSError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(128, 1);
+
+        let (proof, commitment) = {
+            let mut prover_transcript = Transcript::new(b"RangeProofTest");
+            let mut prover = Prover::new(&bp_gens, &pc_gens, &mut prover_transcript);
+
+            let (commitment, variable) =
+                prover.commit(quantity.into(), Scalar::random(&mut rand::thread_rng()));
+            let allocated = AllocatedQuantity {
+                variable,
+                assignment: Some(quantity),
+            };
+
+            range_proof(&mut prover, allocated, n)?;
+
+            (prover.prove()?, commitment)
+        };
+
+        let mut verifier_transcript = Transcript::new(b"RangeProofTest");
+        let mut verifier = Verifier::new(&bp_gens, &pc_gens, &mut verifier_transcript);
+        let variable = verifier.commit(commitment);
+        let allocated = AllocatedQuantity {
+            variable,
+            assignment: None,
+        };
+
+        range_proof(&mut verifier, allocated, n)?;
+
+        Ok(verifier.verify(&proof)?)
+    }
+
+    fn signed_range_proof_helper(quantity: SignedInteger, n: usize) -> Result<(), R1CSError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(128, 1);
+
+        let (proof, commitment) = {
+            let mut prover_transcript = Transcript::new(b"SignedRangeProofTest");
+            let mut prover = Prover::new(&bp_gens, &pc_gens, &mut prover_transcript);
+
+            let (commitment, variable) =
+                prover.commit(quantity.into(), Scalar::random(&mut rand::thread_rng()));
+            let allocated = AllocatedQuantity {
+                variable,
+                assignment: Some(quantity),
+            };
+
+            range_proof_signed(&mut prover, allocated, n)?;
+
+            (prover.prove()?, commitment)
+        };
+
+        let mut verifier_transcript = Transcript::new(b"SignedRangeProofTest");
+        let mut verifier = Verifier::new(&bp_gens, &pc_gens, &mut verifier_transcript);
+        let variable = verifier.commit(commitment);
+        let allocated = AllocatedQuantity {
+            variable,
+            assignment: None,
+        };
+
+        range_proof_signed(&mut verifier, allocated, n)?;
+
+        Ok(verifier.verify(&proof)?)
+    }
+}
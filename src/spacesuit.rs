@@ -2,6 +2,7 @@
 
 use bulletproofs::r1cs::{Prover, R1CSError, R1CSProof, Verifier};
 use bulletproofs::{BulletproofGens, PedersenGens};
+use curve25519_dalek::scalar::Scalar;
 use gadgets::transaction;
 use merlin::Transcript;
 use rand::{CryptoRng, Rng};
@@ -12,8 +13,20 @@ pub fn prove<R: Rng + CryptoRng>(
     pc_gens: &PedersenGens,
     inputs: &Vec<Value>,
     outputs: &Vec<Value>,
+    issuances: &Vec<Value>,
+    fees: &Vec<Value>,
+    allowed_flavors: Option<&[Scalar]>,
     rng: &mut R,
-) -> Result<(R1CSProof, Vec<CommittedValue>, Vec<CommittedValue>), R1CSError>
+) -> Result<
+    (
+        R1CSProof,
+        Vec<CommittedValue>,
+        Vec<CommittedValue>,
+        Vec<CommittedValue>,
+        Vec<CommittedValue>,
+    ),
+    R1CSError,
+>
 where
     R: rand::RngCore,
 {
@@ -22,11 +35,25 @@ where
 
     let (in_com, in_vars) = inputs.commit(&mut prover, rng);
     let (out_com, out_vars) = outputs.commit(&mut prover, rng);
+    let (issuance_com, issuance_vars) = issuances.commit(&mut prover, rng);
+    // Fees are publicly committed (zero blinding factor) so a verifier can read
+    // the paid amount directly, rather than merely trusting a hidden commitment.
+    let (fee_com, fee_vars): (Vec<_>, Vec<_>) = fees
+        .iter()
+        .map(|fee| fee.allocate_public(&mut prover))
+        .unzip();
 
-    transaction::fill_cs(&mut prover, in_vars, out_vars)?;
+    transaction::fill_cs(
+        &mut prover,
+        in_vars,
+        out_vars,
+        issuance_vars,
+        fee_vars,
+        allowed_flavors,
+    )?;
     let proof = prover.prove()?;
 
-    Ok((proof, in_com, out_com))
+    Ok((proof, in_com, out_com, issuance_com, fee_com))
 }
 
 pub fn verify(
@@ -35,6 +62,9 @@ pub fn verify(
     proof: &R1CSProof,
     in_com: &Vec<CommittedValue>,
     out_com: &Vec<CommittedValue>,
+    issuance_com: &Vec<CommittedValue>,
+    fees: &Vec<Value>,
+    allowed_flavors: Option<&[Scalar]>,
 ) -> Result<(), R1CSError> {
     // Verifier makes a `ConstraintSystem` instance representing a merge gadget
     let mut verifier_transcript = Transcript::new(b"TransactionTest");
@@ -42,8 +72,73 @@ pub fn verify(
 
     let in_vars = in_com.commit(&mut verifier);
     let out_vars = out_com.commit(&mut verifier);
+    let issuance_vars = issuance_com.commit(&mut verifier);
+    // Fees are public: the verifier already knows their plaintext value and
+    // rebinds it into the constraint system itself, rather than trusting a
+    // commitment supplied by the prover.
+    let fee_vars: Vec<_> = fees
+        .iter()
+        .map(|fee| fee.allocate_public_for_verifier(&pc_gens, &mut verifier))
+        .collect();
 
-    assert!(transaction::fill_cs(&mut verifier, in_vars, out_vars,).is_ok());
+    transaction::fill_cs(
+        &mut verifier,
+        in_vars,
+        out_vars,
+        issuance_vars,
+        fee_vars,
+        allowed_flavors,
+    )?;
 
     Ok(verifier.verify(&proof)?)
 }
+
+/// Verifies a list of cloak transactions against the shared `bp_gens`/`pc_gens`,
+/// one `verify` call per transaction, short-circuiting on the first failure.
+/// Each transaction carries its own `allowed_flavors` whitelist rather than one
+/// shared across the list.
+///
+/// This is a convenience wrapper, not a batch-verification speedup: this
+/// fork's `Verifier` only exposes `verify(&proof)`, consuming `self`, with no
+/// entry point for folding several transactions' verification equations into
+/// one combined multiscalar multiplication. Call sites that need the actual
+/// near-linear speedup of weighted-MSM batch verification cannot get it from
+/// this function or this bulletproofs fork.
+pub fn verify_each(
+    bp_gens: &BulletproofGens,
+    pc_gens: &PedersenGens,
+    transactions: &[(
+        R1CSProof,
+        Vec<CommittedValue>,
+        Vec<CommittedValue>,
+        Vec<CommittedValue>,
+        Vec<Value>,
+        Option<&[Scalar]>,
+    )],
+) -> Result<(), R1CSError> {
+    for (proof, in_com, out_com, issuance_com, fees, allowed_flavors) in transactions {
+        let mut verifier_transcript = Transcript::new(b"TransactionTest");
+        let mut verifier = Verifier::new(&bp_gens, &pc_gens, &mut verifier_transcript);
+
+        let in_vars = in_com.commit(&mut verifier);
+        let out_vars = out_com.commit(&mut verifier);
+        let issuance_vars = issuance_com.commit(&mut verifier);
+        let fee_vars: Vec<_> = fees
+            .iter()
+            .map(|fee| fee.allocate_public_for_verifier(&pc_gens, &mut verifier))
+            .collect();
+
+        transaction::fill_cs(
+            &mut verifier,
+            in_vars,
+            out_vars,
+            issuance_vars,
+            fee_vars,
+            *allowed_flavors,
+        )?;
+
+        verifier.verify(&proof)?;
+    }
+
+    Ok(())
+}
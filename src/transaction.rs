@@ -0,0 +1,175 @@
+use bulletproofs::r1cs::{R1CSError, R1CSProof};
+use value::{CommittedValue, Value};
+
+/// A serializable bundle of everything `prove` produces: the proof itself,
+/// the public commitments to every input, output, and issuance, and the
+/// *plaintext* fee values. Fees are carried in the clear (not as commitments)
+/// because `verify`/`verify_each` recompute and rebind their zero-blinding
+/// commitment from the plaintext — a node that only had the fee commitment
+/// could not pass it to `verify` and would be unable to check a fee-bearing
+/// transaction at all.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Transaction {
+    pub proof: R1CSProof,
+    pub inputs: Vec<CommittedValue>,
+    pub outputs: Vec<CommittedValue>,
+    pub issuances: Vec<CommittedValue>,
+    pub fees: Vec<Value>,
+}
+
+impl Transaction {
+    /// Serializes the transaction as the length-prefixed input, output, and
+    /// issuance commitment vectors, followed by the length-prefixed plaintext
+    /// fee values, followed by the proof's own byte encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_commitments(&mut bytes, &self.inputs);
+        write_commitments(&mut bytes, &self.outputs);
+        write_commitments(&mut bytes, &self.issuances);
+        write_values(&mut bytes, &self.fees);
+        bytes.extend_from_slice(&self.proof.to_bytes());
+        bytes
+    }
+
+    /// Deserializes a `Transaction` produced by `to_bytes`, surfacing an
+    /// `R1CSError` on truncated input or invalid points or scalars.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, R1CSError> {
+        let mut cursor = bytes;
+        let inputs = read_commitments(&mut cursor)?;
+        let outputs = read_commitments(&mut cursor)?;
+        let issuances = read_commitments(&mut cursor)?;
+        let fees = read_values(&mut cursor)?;
+        let proof = R1CSProof::from_bytes(cursor)?;
+
+        Ok(Transaction {
+            proof,
+            inputs,
+            outputs,
+            issuances,
+            fees,
+        })
+    }
+}
+
+/// Appends a `u32`-length-prefixed list of commitments to `bytes`.
+fn write_commitments(bytes: &mut Vec<u8>, commitments: &[CommittedValue]) {
+    bytes.extend_from_slice(&(commitments.len() as u32).to_le_bytes());
+    for commitment in commitments {
+        bytes.extend_from_slice(&commitment.to_bytes());
+    }
+}
+
+/// Reads a `u32`-length-prefixed list of commitments off the front of
+/// `cursor`, advancing it past the bytes consumed.
+fn read_commitments(cursor: &mut &[u8]) -> Result<Vec<CommittedValue>, R1CSError> {
+    if cursor.len() < 4 {
+        return Err(R1CSError::FormatError);
+    }
+    let (len_bytes, rest) = cursor.split_at(4);
+    let len =
+        u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
+    *cursor = rest;
+
+    if cursor.len() < len * CommittedValue::SERIALIZED_SIZE {
+        return Err(R1CSError::FormatError);
+    }
+
+    let mut commitments = Vec::with_capacity(len);
+    for _ in 0..len {
+        let (chunk, rest) = cursor.split_at(CommittedValue::SERIALIZED_SIZE);
+        commitments.push(CommittedValue::from_bytes(chunk)?);
+        *cursor = rest;
+    }
+    Ok(commitments)
+}
+
+/// Appends a `u32`-length-prefixed list of plaintext values to `bytes`.
+fn write_values(bytes: &mut Vec<u8>, values: &[Value]) {
+    bytes.extend_from_slice(&(values.len() as u32).to_le_bytes());
+    for value in values {
+        bytes.extend_from_slice(&value.to_bytes());
+    }
+}
+
+/// Reads a `u32`-length-prefixed list of plaintext values off the front of
+/// `cursor`, advancing it past the bytes consumed.
+fn read_values(cursor: &mut &[u8]) -> Result<Vec<Value>, R1CSError> {
+    if cursor.len() < 4 {
+        return Err(R1CSError::FormatError);
+    }
+    let (len_bytes, rest) = cursor.split_at(4);
+    let len =
+        u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
+    *cursor = rest;
+
+    if cursor.len() < len * Value::SERIALIZED_SIZE {
+        return Err(R1CSError::FormatError);
+    }
+
+    let mut values = Vec::with_capacity(len);
+    for _ in 0..len {
+        let (chunk, rest) = cursor.split_at(Value::SERIALIZED_SIZE);
+        values.push(Value::from_bytes(chunk)?);
+        *cursor = rest;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bulletproofs::{BulletproofGens, PedersenGens};
+
+    #[test]
+    fn transaction_round_trip() {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(128, 1);
+        let mut rng = rand::thread_rng();
+
+        let inputs = vec![Value {
+            q: 10u64.into(),
+            f: 1u64.into(),
+        }];
+        let outputs = vec![Value {
+            q: 9u64.into(),
+            f: 1u64.into(),
+        }];
+        let fees = vec![Value {
+            q: 1u64.into(),
+            f: 1u64.into(),
+        }];
+
+        let (proof, in_com, out_com, issuance_com, _fee_com) = crate::prove(
+            &bp_gens,
+            &pc_gens,
+            &inputs,
+            &outputs,
+            &vec![],
+            &fees,
+            None,
+            &mut rng,
+        )
+        .unwrap();
+
+        let tx = Transaction {
+            proof,
+            inputs: in_com,
+            outputs: out_com,
+            issuances: issuance_com,
+            fees: fees.clone(),
+        };
+
+        let bytes = tx.to_bytes();
+        let decoded = Transaction::from_bytes(&bytes).unwrap();
+
+        assert_eq!(tx.inputs, decoded.inputs);
+        assert_eq!(tx.outputs, decoded.outputs);
+        assert_eq!(tx.issuances, decoded.issuances);
+        assert_eq!(tx.fees, decoded.fees);
+    }
+
+    #[test]
+    fn from_bytes_rejects_truncated_input() {
+        assert!(Transaction::from_bytes(&[0u8; 3]).is_err());
+    }
+}
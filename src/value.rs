@@ -1,23 +1,123 @@
 use bulletproofs::r1cs::{ConstraintSystem, Prover, R1CSError, Variable, Verifier};
+use bulletproofs::PedersenGens;
+use core::convert::TryInto;
 use core::ops::Neg;
-use curve25519_dalek::ristretto::CompressedRistretto;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
 use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
 use rand::distributions::uniform::{SampleUniform, UniformInt};
 use rand::{CryptoRng, Rng};
 use std::ops::Add;
 use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Value {
     pub q: SignedInteger, // quantity
     pub f: Scalar,        // flavor
 }
 
+impl Value {
+    /// The length in bytes of a `Value`'s canonical encoding: a 1-byte sign
+    /// tag, an 8-byte little-endian magnitude, and a 32-byte scalar.
+    pub const SERIALIZED_SIZE: usize = 41;
+
+    /// Serializes the plaintext quantity and flavor, so a verifier who is
+    /// meant to learn the value (e.g. a public fee) can be handed it directly
+    /// instead of an opaque commitment.
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let (tag, magnitude) = match self.q {
+            SignedInteger::Positive(x) => (0u8, x),
+            SignedInteger::Negative(x) => (1u8, x),
+            SignedInteger::Zero => (2u8, 0u64),
+        };
+
+        let mut bytes = [0u8; Self::SERIALIZED_SIZE];
+        bytes[0] = tag;
+        bytes[1..9].copy_from_slice(&magnitude.to_le_bytes());
+        bytes[9..].copy_from_slice(self.f.as_bytes());
+        bytes
+    }
+
+    /// Deserializes a `Value` from its canonical encoding, rejecting
+    /// malformed input, an invalid sign tag, a non-canonical zero encoding,
+    /// and a non-canonical scalar.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, R1CSError> {
+        if bytes.len() != Self::SERIALIZED_SIZE {
+            return Err(R1CSError::FormatError);
+        }
+
+        let tag = bytes[0];
+        let mut magnitude_bytes = [0u8; 8];
+        magnitude_bytes.copy_from_slice(&bytes[1..9]);
+        let magnitude = u64::from_le_bytes(magnitude_bytes);
+
+        let q = match tag {
+            0 => SignedInteger::Positive(magnitude),
+            1 => {
+                if magnitude == 0 {
+                    return Err(R1CSError::FormatError);
+                }
+                SignedInteger::Negative(magnitude)
+            }
+            2 => {
+                if magnitude != 0 {
+                    return Err(R1CSError::FormatError);
+                }
+                SignedInteger::Zero
+            }
+            _ => return Err(R1CSError::FormatError),
+        };
+
+        let f = Scalar::from_canonical_bytes(bytes[9..].try_into().unwrap())
+            .ok_or(R1CSError::FormatError)?;
+
+        Ok(Value { q, f })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CommittedValue {
     pub q: CompressedRistretto,
     pub f: CompressedRistretto,
 }
 
+impl CommittedValue {
+    /// The length in bytes of a `CommittedValue`'s canonical encoding: two
+    /// compressed Ristretto points.
+    pub const SERIALIZED_SIZE: usize = 64;
+
+    /// Serializes `q` followed by `f`, each as a 32-byte compressed Ristretto point.
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let mut bytes = [0u8; Self::SERIALIZED_SIZE];
+        bytes[..32].copy_from_slice(self.q.as_bytes());
+        bytes[32..].copy_from_slice(self.f.as_bytes());
+        bytes
+    }
+
+    /// Deserializes a `CommittedValue` from its canonical 64-byte encoding,
+    /// rejecting malformed input and non-canonical/invalid points.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, R1CSError> {
+        if bytes.len() != Self::SERIALIZED_SIZE {
+            return Err(R1CSError::FormatError);
+        }
+
+        let q = CompressedRistretto::from_slice(&bytes[..32]);
+        let f = CompressedRistretto::from_slice(&bytes[32..]);
+
+        // Reject points that don't decompress to a valid curve point, and
+        // reject the identity point: a commitment to the identity is a
+        // commitment to zero with zero blinding, which must never be treated
+        // as a legitimate hidden value.
+        let q_point = q.decompress().ok_or(R1CSError::FormatError)?;
+        let f_point = f.decompress().ok_or(R1CSError::FormatError)?;
+        if q_point == RistrettoPoint::identity() || f_point == RistrettoPoint::identity() {
+            return Err(R1CSError::FormatError);
+        }
+
+        Ok(CommittedValue { q, f })
+    }
+}
+
 /// Helper struct for ease of working with
 /// 2-tuples of variables and assignments
 #[derive(Copy, Clone, Debug)]
@@ -36,7 +136,7 @@ pub struct AllocatedQuantity {
 
 /// Represents a signed integer in the range [-(2^64-1) .. 2^64-1]
 /// Zero value is represented as SignedInteger::Positive(0)
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum SignedInteger {
     Positive(u64),
     Negative(u64),
@@ -64,6 +164,55 @@ impl Value {
         })
     }
 
+    /// Computes the commitment `allocate_public` and `allocate_public_for_verifier`
+    /// agree on: a Pedersen commitment with a zero blinding factor. Since anyone who
+    /// knows `self` can recompute this point themselves, it hides nothing — it lets a
+    /// verifier bind a publicly known value (e.g. a fee) into the constraint system
+    /// without trusting a commitment supplied by the prover.
+    pub fn public_commitment(&self, pc_gens: &PedersenGens) -> CommittedValue {
+        CommittedValue {
+            q: pc_gens.commit(self.q.into(), Scalar::zero()).compress(),
+            f: pc_gens.commit(self.f, Scalar::zero()).compress(),
+        }
+    }
+
+    /// Commits `self` with a zero blinding factor and allocates it in the prover's
+    /// constraint system, for values the verifier is meant to learn (see
+    /// `public_commitment`). Unlike `ProverCommittable::commit`, the returned
+    /// commitment carries no hiding: a verifier holding the same plaintext `Value`
+    /// can recompute it with `public_commitment` and check it matches.
+    pub fn allocate_public(&self, prover: &mut Prover) -> (CommittedValue, AllocatedValue) {
+        let (q_commit, q_var) = prover.commit(self.q.into(), Scalar::zero());
+        let (f_commit, f_var) = prover.commit(self.f, Scalar::zero());
+        let commitments = CommittedValue {
+            q: q_commit,
+            f: f_commit,
+        };
+        let vars = AllocatedValue {
+            q: q_var,
+            f: f_var,
+            assignment: Some(*self),
+        };
+        (commitments, vars)
+    }
+
+    /// Recomputes the zero-blinding commitment to `self` and allocates it in the
+    /// verifier's constraint system, binding a value the verifier already knows in
+    /// cleartext (see `public_commitment`) into the proof instead of trusting a
+    /// commitment supplied by the prover.
+    pub fn allocate_public_for_verifier(
+        &self,
+        pc_gens: &PedersenGens,
+        verifier: &mut Verifier,
+    ) -> AllocatedValue {
+        let commitment = self.public_commitment(pc_gens);
+        AllocatedValue {
+            q: verifier.commit(commitment.q),
+            f: verifier.commit(commitment.f),
+            assignment: None,
+        }
+    }
+
     pub fn allocate_unassigned<CS: ConstraintSystem>(
         cs: &mut CS,
     ) -> Result<AllocatedValue, R1CSError> {
@@ -116,7 +265,7 @@ impl SignedInteger {
         }
     }
 
-    fn to_i128(&self) -> i128 {
+    pub(crate) fn to_i128(&self) -> i128 {
         match self {
             SignedInteger::Positive(x) => (*x).into(),
             SignedInteger::Negative(x) => -(*x as i128),